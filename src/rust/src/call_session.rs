@@ -0,0 +1,275 @@
+//
+// Copyright 2019-2021 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Typed call/session event stream.
+//!
+//! The raw subscription hands back envelopes the app must dispatch by hand, and
+//! `CallStateHandler`/`GroupUpdateHandler` are callback traits rather than
+//! something you can `select!` on.  [`CallSession`] wraps the `CallManager`
+//! handlers behind a channel and emits a typed [`CallEvent`] stream, modeled on
+//! connection/stream session APIs, so the bridge can await call lifecycle,
+//! connection, and per-participant events ergonomically.
+//!
+//! A lightweight app-to-app [`AppSignal`] (typed tag + opaque payload) rides the
+//! same [`SignalingTransport`] so callers can send custom in-call signaling
+//! without a separate channel.
+
+use log::{debug, warn};
+use tokio::sync::mpsc;
+
+use ringrtc::common::{CallId, CallMediaType, Result};
+use ringrtc::lite::sfu::UserId;
+use ringrtc::native::{CallState, CallStateHandler, GroupUpdate, GroupUpdateHandler, PeerId};
+use ringrtc::webrtc::peer_connection::AudioLevel;
+use ringrtc::webrtc::peer_connection_observer::NetworkRoute;
+
+use crate::transport::{SignalingMessage, SignalingTransport, TransportEvent};
+
+/// A typed event emitted by a [`CallSession`].
+#[derive(Clone, Debug)]
+pub enum CallEvent {
+    /// An inbound call is ringing.
+    IncomingCall {
+        call_id:    CallId,
+        caller:     PeerId,
+        media_type: CallMediaType,
+    },
+    /// The remote offered or answered with a session description.
+    RemoteSessionDescription(String),
+    /// The remote sent ICE candidate updates.
+    RemoteIceCandidates(Vec<String>),
+    /// The active network route changed.
+    ConnectionStateChanged(NetworkRoute),
+    /// A remote audio level sample.
+    RemoteAudioLevel(AudioLevel),
+    /// The call ended.
+    CallEnded { reason: String },
+    /// A participant joined a group call.
+    ParticipantJoined(UserId),
+    /// A participant left a group call.
+    ParticipantLeft(UserId),
+    /// An app-to-app signal received from the peer.
+    Signal(AppSignal),
+}
+
+/// A lightweight app-to-app in-call message: a typed tag plus opaque payload.
+#[derive(Clone, Debug)]
+pub struct AppSignal {
+    /// Application-defined message tag.
+    pub tag:     String,
+    /// Opaque payload bytes.
+    pub payload: Vec<u8>,
+}
+
+/// Forwards `CallManager` callbacks into a [`CallEvent`] channel.
+///
+/// Installed as both the `CallStateHandler` and `GroupUpdateHandler` on the
+/// native platform; each callback maps to a [`CallEvent`] and is pushed onto
+/// the session's channel.
+#[derive(Clone)]
+struct EventSink {
+    sender: mpsc::UnboundedSender<CallEvent>,
+}
+
+impl EventSink {
+    fn emit(&self, event: CallEvent) {
+        // A closed receiver just means the session was dropped; drop the event.
+        let _ = self.sender.send(event);
+    }
+}
+
+impl CallStateHandler for EventSink {
+    fn handle_call_state(
+        &self,
+        remote_peer_id: &PeerId,
+        call_id: CallId,
+        call_state: CallState,
+    ) -> Result<()> {
+        match call_state {
+            CallState::Incoming(media_type) => self.emit(CallEvent::IncomingCall {
+                call_id,
+                caller: remote_peer_id.clone(),
+                media_type,
+            }),
+            CallState::Ended(reason) => self.emit(CallEvent::CallEnded {
+                reason: format!("{:?}", reason),
+            }),
+            other => debug!("unmapped call state: {:?}", other),
+        }
+        Ok(())
+    }
+
+    fn handle_network_route(
+        &self,
+        _remote_peer_id: &PeerId,
+        network_route: NetworkRoute,
+    ) -> Result<()> {
+        self.emit(CallEvent::ConnectionStateChanged(network_route));
+        Ok(())
+    }
+
+    fn handle_remote_audio_level(
+        &self,
+        _remote_peer_id: &PeerId,
+        audio_level: AudioLevel,
+    ) -> Result<()> {
+        self.emit(CallEvent::RemoteAudioLevel(audio_level));
+        Ok(())
+    }
+}
+
+impl GroupUpdateHandler for EventSink {
+    fn handle_group_update(&self, update: GroupUpdate) -> Result<()> {
+        match update {
+            GroupUpdate::ParticipantJoined(user_id) => {
+                self.emit(CallEvent::ParticipantJoined(user_id))
+            }
+            GroupUpdate::ParticipantLeft(user_id) => {
+                self.emit(CallEvent::ParticipantLeft(user_id))
+            }
+            other => debug!("unmapped group update: {:?}", other),
+        }
+        Ok(())
+    }
+}
+
+/// An async view over a call: a typed event stream plus app-signal sending.
+///
+/// [`next_event`](Self::next_event) merges two sources into one stream the
+/// caller can await directly: call lifecycle/connection events fed by the
+/// `CallManager` handlers, and inbound signaling (remote SDP, remote ICE, and
+/// app-to-app signals) read off the [`SignalingTransport`].
+pub struct CallSession {
+    events:          mpsc::UnboundedReceiver<CallEvent>,
+    transport:       Box<dyn SignalingTransport>,
+    /// Cleared once the callback channel closes so we stop polling it.
+    events_open:     bool,
+    /// Cleared once the transport closes so we stop polling it.
+    transport_open:  bool,
+}
+
+impl CallSession {
+    /// Create a session over `transport`, returning the session and the
+    /// handlers to install on the native platform's `CallManager`.
+    pub fn new(
+        transport: Box<dyn SignalingTransport>,
+    ) -> (Self, Box<dyn CallStateHandler>, Box<dyn GroupUpdateHandler>) {
+        let (sender, events) = mpsc::unbounded_channel();
+        let sink = EventSink { sender };
+        let session = Self {
+            events,
+            transport,
+            events_open: true,
+            transport_open: true,
+        };
+        (session, Box::new(sink.clone()), Box::new(sink))
+    }
+
+    /// Await the next call event, combining `CallManager` callbacks with
+    /// inbound signaling from the transport, or `None` once both sources are
+    /// exhausted.
+    pub async fn next_event(&mut self) -> Option<CallEvent> {
+        loop {
+            match (self.events_open, self.transport_open) {
+                // Both sources exhausted: the merged stream ends.
+                (false, false) => return None,
+                // Only the callback channel remains.
+                (true, false) => return self.recv_callback().await,
+                // Only the transport remains.
+                (false, true) => {
+                    if let Some(event) = self.recv_transport().await {
+                        return Some(event);
+                    }
+                }
+                // Both live: await whichever fires first, but do not end the
+                // stream just because one side closed — keep draining the other.
+                (true, true) => {
+                    tokio::select! {
+                        event = self.events.recv() => match event {
+                            Some(event) => return Some(event),
+                            None => self.events_open = false,
+                        },
+                        incoming = self.transport.recv() => {
+                            if let Some(event) = self.handle_transport(incoming) {
+                                return Some(event);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Await a callback event, marking the callback side done on channel close.
+    async fn recv_callback(&mut self) -> Option<CallEvent> {
+        let event = self.events.recv().await;
+        if event.is_none() {
+            self.events_open = false;
+        }
+        event
+    }
+
+    /// Await one transport event, mapping it to a [`CallEvent`] when applicable.
+    async fn recv_transport(&mut self) -> Option<CallEvent> {
+        let incoming = self.transport.recv().await;
+        self.handle_transport(incoming)
+    }
+
+    /// Fold a transport `recv` result into the stream: map a surfaced event, or
+    /// mark the transport side done on close/error.
+    fn handle_transport(
+        &mut self,
+        incoming: Result<Option<TransportEvent>>,
+    ) -> Option<CallEvent> {
+        match incoming {
+            Ok(Some(event)) => Self::map_transport_event(event),
+            Ok(None) => {
+                self.transport_open = false;
+                None
+            }
+            Err(e) => {
+                warn!("signaling transport error: {}", e);
+                self.transport_open = false;
+                None
+            }
+        }
+    }
+
+    /// Map an inbound [`TransportEvent`] to a [`CallEvent`].  Every transport
+    /// event is surfaced on the session stream, so this always returns `Some`.
+    fn map_transport_event(event: TransportEvent) -> Option<CallEvent> {
+        Some(match event {
+            TransportEvent::Message(SignalingMessage::Offer { sdp, .. })
+            | TransportEvent::Message(SignalingMessage::Answer { sdp, .. }) => {
+                CallEvent::RemoteSessionDescription(sdp)
+            }
+            TransportEvent::Message(SignalingMessage::Ice { candidates, .. }) => {
+                CallEvent::RemoteIceCandidates(candidates)
+            }
+            TransportEvent::Message(SignalingMessage::Hangup { .. }) => CallEvent::CallEnded {
+                reason: "remote hangup".to_string(),
+            },
+            TransportEvent::Message(SignalingMessage::Signal { tag, payload }) => {
+                CallEvent::Signal(AppSignal { tag, payload })
+            }
+            TransportEvent::ParticipantJoined(identity) => {
+                CallEvent::ParticipantJoined(identity.into_bytes())
+            }
+            TransportEvent::ParticipantLeft(identity) => {
+                CallEvent::ParticipantLeft(identity.into_bytes())
+            }
+        })
+    }
+
+    /// Send an app-to-app signal to the peer over the signaling transport.
+    pub async fn send_signal(&mut self, signal: AppSignal) -> Result<()> {
+        self.transport
+            .send(SignalingMessage::Signal {
+                tag:     signal.tag,
+                payload: signal.payload,
+            })
+            .await
+    }
+}
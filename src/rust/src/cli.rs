@@ -5,37 +5,20 @@
 
 use log::{debug, info};
 
-use ringrtc::{
-    common::{
-        actor::{Actor, Stopper},
-        units::DataRate,
-        CallId, CallMediaType, DeviceId, Result,
-    },
-    core::{bandwidth_mode::BandwidthMode, call_manager::CallManager, group_call, signaling},
-    lite::{http, sfu::UserId},
-    native::{
-        CallState, CallStateHandler, GroupUpdate, GroupUpdateHandler, NativeCallContext,
-        NativePlatform, PeerId, SignalingSender,
-    },
-    simnet::{
-        router,
-        router::{LinkConfig, Router},
-    },
-    webrtc::{
-        injectable_network,
-        injectable_network::InjectableNetwork,
-        media::{VideoFrame, VideoPixelFormat, VideoSink, VideoSource},
-        network::NetworkInterfaceType,
-        peer_connection::AudioLevel,
-        peer_connection_factory::{self as pcf, IceServer, PeerConnectionFactory},
-        peer_connection_observer::NetworkRoute,
-    },
-};
-
-use signald::types::{ClientMessageWrapperV1, SubscribeRequestV1};
-use signald::Signald;
-
-const ACCOUNT: &str = "+17346081614";
+use ringrtc::common::Result;
+
+mod call_session;
+mod signald_transport;
+mod transport;
+mod ws_transport;
+
+use call_session::{AppSignal, CallEvent, CallSession};
+use transport::JoinInfo;
+
+/// Signaling service to connect to.  A `ws://`/`wss://` URL selects the
+/// WebSocket/room backend; anything else is a `signald` socket path.  Override
+/// with the `SIGNALING_URL` environment variable.
+const DEFAULT_SIGNALING_URL: &str = "/signald/signald.sock";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -49,12 +32,57 @@ async fn main() -> Result<()> {
     #[cfg(not(debug_assertions))]
     ringrtc::webrtc::logging::set_logger(log::LevelFilter::Warn);
 
-    info!("connecting to signald");
-    let mut socket = Signald::connect("/signald/signald.sock").await?;
+    let url = std::env::var("SIGNALING_URL").unwrap_or_else(|_| DEFAULT_SIGNALING_URL.to_string());
+
+    // Room-based backends need join credentials; supply them when present.
+    let join = match (std::env::var("ROOM"), std::env::var("ACCESS_TOKEN")) {
+        (Ok(room), Ok(token)) => Some(JoinInfo {
+            room,
+            identity: std::env::var("IDENTITY").unwrap_or_default(),
+            token,
+        }),
+        _ => None,
+    };
 
-    info!("subscribing to messages");
-    let mut subscribe = SubscribeRequestV1::default();
-    subscribe.account = Some(ACCOUNT.into());
+    info!("connecting signaling transport: {}", url);
+    let signaling = transport::connect(&url, join).await?;
+
+    // Wrap the transport in a typed session.  The returned handlers would be
+    // installed on the native platform's `CallManager`; here we drive the
+    // event stream directly.
+    let (mut session, _call_handler, _group_handler) = CallSession::new(signaling);
+
+    info!("receiving call events");
+    while let Some(event) = session.next_event().await {
+        match event {
+            CallEvent::IncomingCall {
+                call_id,
+                caller,
+                media_type,
+            } => {
+                info!("incoming {:?} call {:?} from {}", media_type, call_id, caller);
+                // Acknowledge the call over the app-to-app signal channel.
+                session
+                    .send_signal(AppSignal {
+                        tag:     "ack".to_string(),
+                        payload: Vec::new(),
+                    })
+                    .await?;
+            }
+            CallEvent::RemoteSessionDescription(sdp) => {
+                debug!("remote session description ({} bytes)", sdp.len())
+            }
+            CallEvent::RemoteIceCandidates(candidates) => {
+                debug!("remote ice candidates: {}", candidates.len())
+            }
+            CallEvent::ConnectionStateChanged(route) => info!("network route: {:?}", route),
+            CallEvent::RemoteAudioLevel(level) => debug!("remote audio level: {:?}", level),
+            CallEvent::CallEnded { reason } => info!("call ended: {}", reason),
+            CallEvent::ParticipantJoined(id) => info!("participant joined: {:?}", id),
+            CallEvent::ParticipantLeft(id) => info!("participant left: {:?}", id),
+            CallEvent::Signal(signal) => debug!("app signal: {}", signal.tag),
+        }
+    }
 
     Ok(())
 }
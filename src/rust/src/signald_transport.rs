@@ -0,0 +1,84 @@
+//
+// Copyright 2019-2021 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! `signald` Unix-socket [`SignalingTransport`].
+//!
+//! Wraps the existing `Signald::connect` / `SubscribeRequestV1` flow behind the
+//! transport trait so it becomes one backend among several rather than the only
+//! way to exchange signaling.  Our crate-local [`SignalingMessage`] is carried
+//! as the JSON `data` payload of a signald [`ClientMessageWrapperV1`] envelope:
+//! outbound messages are serialized into a wrapper and submitted; inbound
+//! wrappers are deserialized back out of their `data` field.
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use ringrtc::common::Result;
+
+use signald::types::{ClientMessageWrapperV1, SubscribeRequestV1};
+use signald::Signald;
+
+use crate::transport::{SignalingMessage, SignalingTransport, TransportEvent};
+
+const ACCOUNT: &str = "+17346081614";
+
+/// signald envelope type tag used for RingRTC signaling payloads.
+const SIGNALING_TYPE: &str = "ringrtc-signaling";
+
+/// [`SignalingTransport`] backed by a local `signald` Unix socket.
+pub struct SignaldTransport {
+    socket: Signald,
+}
+
+impl SignaldTransport {
+    /// Connect to the `signald` socket at `path` and subscribe to messages.
+    pub async fn connect(path: &str) -> Result<Self> {
+        let mut socket = Signald::connect(path).await?;
+
+        let mut subscribe = SubscribeRequestV1::default();
+        subscribe.account = Some(ACCOUNT.into());
+        socket.subscribe(subscribe).await?;
+
+        Ok(Self { socket })
+    }
+}
+
+/// Wrap a [`SignalingMessage`] in a signald envelope for submission.
+fn encode(message: &SignalingMessage) -> Result<ClientMessageWrapperV1> {
+    let mut wrapper = ClientMessageWrapperV1::default();
+    wrapper.version = Some("v1".into());
+    wrapper.r#type = Some(SIGNALING_TYPE.into());
+    wrapper.account = Some(ACCOUNT.into());
+    wrapper.data = Some(serde_json::to_value(message)?);
+    Ok(wrapper)
+}
+
+/// Extract a [`SignalingMessage`] from a signald envelope, ignoring envelopes
+/// that are not RingRTC signaling (e.g. receipts, typing indicators).
+fn decode(wrapper: ClientMessageWrapperV1) -> Option<SignalingMessage> {
+    if wrapper.r#type.as_deref() != Some(SIGNALING_TYPE) {
+        return None;
+    }
+    serde_json::from_value(wrapper.data?).ok()
+}
+
+#[async_trait]
+impl SignalingTransport for SignaldTransport {
+    async fn send(&mut self, message: SignalingMessage) -> Result<()> {
+        self.socket.send(encode(&message)?).await?;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> Result<Option<TransportEvent>> {
+        // Skip envelopes that are not RingRTC signaling until one decodes or the
+        // stream closes.
+        while let Some(wrapper) = self.socket.next().await {
+            if let Some(message) = decode(wrapper?) {
+                return Ok(Some(TransportEvent::Message(message)));
+            }
+        }
+        Ok(None)
+    }
+}
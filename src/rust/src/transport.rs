@@ -0,0 +1,117 @@
+//
+// Copyright 2019-2021 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Pluggable signaling transport.
+//!
+//! The bridge needs to exchange offers, answers, ICE updates, and hangups with
+//! a peer, and to join/leave group calls.  Historically that was wired
+//! directly to the `signald` Unix socket (`Signald::connect`,
+//! `SubscribeRequestV1`).  [`SignalingTransport`] abstracts those operations so
+//! the crate can also drive calls against non-signald deployments — e.g. a
+//! room-based SFU signaller reached over a WebSocket.  A transport is selected
+//! at startup with [`connect`]; nothing here wires it into the native
+//! platform's `SignalingSender`.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use ringrtc::common::{CallId, Result};
+
+use crate::ws_transport::WebSocketTransport;
+use crate::signald_transport::SignaldTransport;
+
+/// A signaling message exchanged with the remote service.
+///
+/// Payloads are carried opaquely: each transport is responsible only for
+/// framing and delivery, not for interpreting the WebRTC contents.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignalingMessage {
+    /// A call offer.
+    Offer { call_id: u64, sdp: String },
+    /// A call answer.
+    Answer { call_id: u64, sdp: String },
+    /// One or more ICE candidate updates.
+    Ice { call_id: u64, candidates: Vec<String> },
+    /// A hangup for the given call.
+    Hangup { call_id: u64 },
+    /// A lightweight app-to-app signal: a typed tag plus opaque payload.
+    Signal { tag: String, payload: Vec<u8> },
+}
+
+impl SignalingMessage {
+    /// The call this message belongs to, if any.  App-to-app signals are not
+    /// tied to a specific call.
+    pub fn call_id(&self) -> Option<CallId> {
+        let id = match self {
+            SignalingMessage::Offer { call_id, .. } => *call_id,
+            SignalingMessage::Answer { call_id, .. } => *call_id,
+            SignalingMessage::Ice { call_id, .. } => *call_id,
+            SignalingMessage::Hangup { call_id } => *call_id,
+            SignalingMessage::Signal { .. } => return None,
+        };
+        Some(CallId::new(id))
+    }
+}
+
+/// Identity and credentials for joining a room-based group call.
+#[derive(Clone, Debug)]
+pub struct JoinInfo {
+    /// Room the call lives in.
+    pub room:     String,
+    /// Our identity within the room.
+    pub identity: String,
+    /// Access token presented on join.
+    pub token:    String,
+}
+
+/// Events surfaced by a transport: inbound signaling plus group membership
+/// changes.
+#[derive(Clone, Debug)]
+pub enum TransportEvent {
+    /// An inbound signaling message.
+    Message(SignalingMessage),
+    /// A participant joined the group call.
+    ParticipantJoined(String),
+    /// A participant left the group call.
+    ParticipantLeft(String),
+}
+
+/// Abstract send/receive of signaling messages and group-call membership.
+///
+/// A transport is driven as an async source: outbound calls are `send`/`join`/
+/// `leave`, and inbound traffic is pulled one event at a time with
+/// [`recv`](SignalingTransport::recv) so callers can `select!` on it.
+#[async_trait]
+pub trait SignalingTransport: Send {
+    /// Send a signaling message to the peer.
+    async fn send(&mut self, message: SignalingMessage) -> Result<()>;
+
+    /// Join a group call.  A no-op for transports without group semantics.
+    async fn join(&mut self, _info: &JoinInfo) -> Result<()> {
+        Ok(())
+    }
+
+    /// Leave the current group call.  A no-op for transports without group
+    /// semantics.
+    async fn leave(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Await the next inbound event, or `None` once the transport closes.
+    async fn recv(&mut self) -> Result<Option<TransportEvent>>;
+}
+
+/// Select a transport at startup from a connection URL.
+///
+/// A `ws://` or `wss://` URL yields a [`WebSocketTransport`]; anything else is
+/// treated as a path to the `signald` Unix socket.
+pub async fn connect(url: &str, join: Option<JoinInfo>) -> Result<Box<dyn SignalingTransport>> {
+    if url.starts_with("ws://") || url.starts_with("wss://") {
+        Ok(Box::new(WebSocketTransport::connect(url, join).await?))
+    } else {
+        Ok(Box::new(SignaldTransport::connect(url).await?))
+    }
+}
@@ -0,0 +1,673 @@
+//
+// Copyright (C) 2019 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Structured SDP parsing and munging.
+//!
+//! `SessionDescriptionInterface` only hands back the whole SDP blob as an
+//! opaque string.  WebRTC generates that blob however it likes; the signald
+//! bridge sometimes needs to apply codec and bitrate policy on top (force VP8
+//! over H264, clamp the bandwidth, strip unwanted attributes) before the
+//! description is handed back to `create_sdp_offer`/`create_sdp_answer`.
+//!
+//! Rather than string-hacking, we parse the offer/answer into an [`SdpSession`],
+//! mutate the model, and re-serialize with [`SdpSession::serialize`].  Parsing
+//! is line-oriented and content-preserving: any line we do not understand is
+//! carried verbatim inside the section that owns it.  Serialization emits lines
+//! in canonical RFC 4566 order (codec `a=rtpmap`/`a=fmtp` grouped per codec,
+//! generic attributes in their original relative order), which WebRTC's own
+//! deserializer accepts.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::common::Result;
+
+/// Errors produced while parsing, validating, or serializing SDP.
+///
+/// Kept local to this self-contained module; like the rest of the crate it is
+/// surfaced through the boxed [`crate::common::Result`] error via `.into()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SdpError {
+    /// A line could not be parsed into the SDP model.
+    Parse(String),
+    /// The session is not safe to serialize (e.g. an empty m-section or a
+    /// dangling payload type).
+    Validate(String),
+}
+
+impl fmt::Display for SdpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SdpError::Parse(what) => write!(f, "failed to parse SDP: {}", what),
+            SdpError::Validate(why) => write!(f, "invalid SDP: {}", why),
+        }
+    }
+}
+
+impl Error for SdpError {}
+
+/// A session- or media-level `b=` bandwidth line, e.g. `b=AS:256`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SdpBandwidth {
+    /// Bandwidth modifier, e.g. `AS` or `TIAS`.
+    pub kind:  String,
+    /// Bandwidth value.  `AS` is in kilobits/sec, `TIAS` in bits/sec.
+    pub value: u64,
+}
+
+impl fmt::Display for SdpBandwidth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "b={}:{}", self.kind, self.value)
+    }
+}
+
+/// A single codec within an `m=` section: the payload type plus its optional
+/// `a=rtpmap`/`a=fmtp` parameter lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SdpCodec {
+    /// RTP payload type number referenced by the `m=` format list.
+    pub payload_type: u32,
+    /// Value of the `a=rtpmap:<pt> ...` line, e.g. `VP8/90000`.
+    pub rtpmap:       Option<String>,
+    /// Value of the `a=fmtp:<pt> ...` line.
+    pub fmtp:         Option<String>,
+}
+
+impl SdpCodec {
+    /// Return the encoding name (the token before the first `/` in the
+    /// rtpmap), upper-cased for case-insensitive comparison.
+    fn encoding_name(&self) -> Option<String> {
+        self.rtpmap
+            .as_ref()
+            .and_then(|m| m.split('/').next())
+            .map(|n| n.to_uppercase())
+    }
+}
+
+/// A single `m=` media section together with everything that belongs to it.
+#[derive(Clone, Debug)]
+pub struct SdpMediaSection {
+    /// Media type, e.g. `audio` or `video`.
+    pub media_type: String,
+    /// Transport port field of the `m=` line.
+    pub port:       String,
+    /// Transport protocol, e.g. `UDP/TLS/RTP/SAVPF`.
+    pub protocol:   String,
+    /// Bandwidth lines for this section.
+    pub bandwidths: Vec<SdpBandwidth>,
+    /// Codecs carried by this section, in payload-order.
+    pub codecs:     Vec<SdpCodec>,
+    /// Generic `a=` attributes that are not codec rtpmap/fmtp lines, kept in
+    /// their original relative order.
+    pub attributes: Vec<String>,
+    /// Lines we do not model (`c=`, extra session lines, ...) kept verbatim so
+    /// serialization is lossless.
+    pub verbatim:   Vec<String>,
+}
+
+impl SdpMediaSection {
+    /// Move every codec whose encoding name matches `name` (case-insensitive)
+    /// to the front of the codec list, keeping relative order otherwise.  Used
+    /// to force e.g. VP8 ahead of H264.
+    pub fn prefer_codec(&mut self, name: &str) {
+        let name = name.to_uppercase();
+        self.codecs.sort_by_key(|c| {
+            c.encoding_name().map(|n| n != name).unwrap_or(true)
+        });
+    }
+
+    /// Remove every codec whose encoding name matches `name`
+    /// (case-insensitive).
+    pub fn remove_codec(&mut self, name: &str) {
+        let name = name.to_uppercase();
+        self.codecs
+            .retain(|c| c.encoding_name().map(|n| n != name).unwrap_or(true));
+    }
+
+    /// Set (replacing any existing) a bandwidth line of the given kind.
+    pub fn set_bandwidth(&mut self, kind: &str, value: u64) {
+        self.bandwidths.retain(|b| b.kind != kind);
+        self.bandwidths.push(SdpBandwidth {
+            kind: kind.to_string(),
+            value,
+        });
+    }
+
+    /// Drop every generic attribute whose `a=` name matches `name`, e.g.
+    /// `rtcp-fb` or `extmap`.
+    pub fn remove_attribute(&mut self, name: &str) {
+        self.attributes.retain(|a| attribute_name(a) != name);
+    }
+
+    /// Render this section back to SDP lines in canonical order: `m=`, any
+    /// verbatim lines (e.g. `c=`), bandwidths, then each codec's
+    /// `a=rtpmap`/`a=fmtp`, then the generic attributes in their original
+    /// relative order.
+    fn write(&self, out: &mut String) {
+        let fmts: Vec<String> = self
+            .codecs
+            .iter()
+            .map(|c| c.payload_type.to_string())
+            .collect();
+        out.push_str(&format!(
+            "m={} {} {} {}\r\n",
+            self.media_type,
+            self.port,
+            self.protocol,
+            fmts.join(" ")
+        ));
+        for line in &self.verbatim {
+            out.push_str(line);
+            out.push_str("\r\n");
+        }
+        for bw in &self.bandwidths {
+            out.push_str(&bw.to_string());
+            out.push_str("\r\n");
+        }
+        for codec in &self.codecs {
+            if let Some(rtpmap) = &codec.rtpmap {
+                out.push_str(&format!("a=rtpmap:{} {}\r\n", codec.payload_type, rtpmap));
+            }
+            if let Some(fmtp) = &codec.fmtp {
+                out.push_str(&format!("a=fmtp:{} {}\r\n", codec.payload_type, fmtp));
+            }
+        }
+        for attr in &self.attributes {
+            out.push_str("a=");
+            out.push_str(attr);
+            out.push_str("\r\n");
+        }
+    }
+}
+
+/// A parsed SDP session description.
+#[derive(Clone, Debug)]
+pub struct SdpSession {
+    /// Value of the `o=` origin line.
+    pub origin:     String,
+    /// Value of the `t=` timing line.
+    pub timing:     String,
+    /// Session-level bandwidth lines.
+    pub bandwidths: Vec<SdpBandwidth>,
+    /// The media sections, in order.
+    pub media:      Vec<SdpMediaSection>,
+    /// Session-level lines preceding the first `m=` that we keep verbatim
+    /// (`v=`, `s=`, `c=`, session `a=`, ...), so serialization is lossless.
+    session:        Vec<String>,
+}
+
+impl SdpSession {
+    /// Parse an SDP string into a structured session.
+    ///
+    /// Lines are handled one at a time (`v=/o=/s=/t=/b=/m=/a=/c=`).  Anything
+    /// before the first `m=` is session-level; everything after belongs to the
+    /// most recent media section.  Unknown lines are preserved verbatim.
+    pub fn parse(sdp: &str) -> Result<Self> {
+        let mut origin = String::new();
+        let mut timing = String::new();
+        let mut bandwidths = Vec::new();
+        let mut session = Vec::new();
+        let mut media: Vec<SdpMediaSection> = Vec::new();
+
+        for raw in sdp.lines() {
+            let line = raw.trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some(kv) => kv,
+                None => return Err(SdpError::Parse(line.to_string()).into()),
+            };
+            match (key, media.last_mut()) {
+                ("m", _) => {
+                    let mut parts = value.splitn(4, ' ');
+                    let media_type = parts.next().unwrap_or("").to_string();
+                    let port = parts.next().unwrap_or("").to_string();
+                    let protocol = parts.next().unwrap_or("").to_string();
+                    let codecs = parts
+                        .next()
+                        .unwrap_or("")
+                        .split_whitespace()
+                        .filter_map(|pt| pt.parse::<u32>().ok())
+                        .map(|payload_type| SdpCodec {
+                            payload_type,
+                            rtpmap: None,
+                            fmtp: None,
+                        })
+                        .collect();
+                    media.push(SdpMediaSection {
+                        media_type,
+                        port,
+                        protocol,
+                        bandwidths: Vec::new(),
+                        codecs,
+                        attributes: Vec::new(),
+                        verbatim: Vec::new(),
+                    });
+                }
+                ("o", None) => origin = value.to_string(),
+                ("t", None) => timing = value.to_string(),
+                ("b", None) => bandwidths.push(parse_bandwidth(value)?),
+                ("b", Some(m)) => m.bandwidths.push(parse_bandwidth(value)?),
+                ("a", Some(m)) => apply_media_attribute(m, value),
+                (_, Some(m)) => m.verbatim.push(line.to_string()),
+                (_, None) => session.push(line.to_string()),
+            }
+        }
+
+        let parsed = Self {
+            origin,
+            timing,
+            bandwidths,
+            media,
+            session,
+        };
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    /// Convenience accessors for the first media section of a given type.
+    pub fn media_mut(&mut self, media_type: &str) -> Option<&mut SdpMediaSection> {
+        self.media.iter_mut().find(|m| m.media_type == media_type)
+    }
+
+    /// Reject sessions that cannot be safely serialized: a media section whose
+    /// codecs have all been removed, or a dangling `a=rtpmap`/`a=fmtp` whose
+    /// payload type has no entry in the section's `m=` format list.
+    fn validate(&self) -> Result<()> {
+        for m in &self.media {
+            if m.codecs.is_empty() {
+                return Err(SdpError::Validate(format!(
+                    "m={} section left with no codecs",
+                    m.media_type
+                ))
+                .into());
+            }
+            for attr in &m.attributes {
+                if let Some(pt) = dangling_payload_type(m, attr) {
+                    return Err(SdpError::Validate(format!(
+                        "m={} references payload type {} with no m= entry",
+                        m.media_type, pt
+                    ))
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reassemble the session into a valid SDP string.
+    ///
+    /// Named `serialize` rather than `to_string` so it does not shadow the
+    /// [`ToString`] impl generated by [`fmt::Display`]; the two return
+    /// different types (`Result<String>` vs `String`).
+    ///
+    /// Session-level lines are emitted in RFC 4566 order: `v= o= s= … c= b=
+    /// t= … a=`.  In particular the session bandwidth and timing lines land
+    /// before any session `a=` attributes, so WebRTC's order-sensitive
+    /// deserializer accepts the result.
+    pub fn serialize(&self) -> Result<String> {
+        self.validate()?;
+        let mut out = String::new();
+        // Split the verbatim session lines around the `t=` line: everything up
+        // to and including `c=` precedes `b=`/`t=`; `a=` (and `r=/z=/k=`) must
+        // follow `t=`.
+        let mut wrote_origin = false;
+        for line in self.session.iter().filter(|l| !is_post_timing(l)) {
+            out.push_str(line);
+            out.push_str("\r\n");
+            if line.starts_with("v=") && !wrote_origin {
+                out.push_str(&format!("o={}\r\n", self.origin));
+                wrote_origin = true;
+            }
+        }
+        if !wrote_origin {
+            out.push_str(&format!("o={}\r\n", self.origin));
+        }
+        for bw in &self.bandwidths {
+            out.push_str(&bw.to_string());
+            out.push_str("\r\n");
+        }
+        out.push_str(&format!("t={}\r\n", self.timing));
+        for line in self.session.iter().filter(|l| is_post_timing(l)) {
+            out.push_str(line);
+            out.push_str("\r\n");
+        }
+        for m in &self.media {
+            m.write(&mut out);
+        }
+        Ok(out)
+    }
+}
+
+impl fmt::Display for SdpSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.serialize() {
+            Ok(s) => f.write_str(&s),
+            Err(e) => write!(f, "<invalid sdp: {}>", e),
+        }
+    }
+}
+
+/// If `attr` is an `rtpmap:`/`fmtp:` line whose payload type is absent from the
+/// section's `m=` format list, return that dangling payload type.
+fn dangling_payload_type(m: &SdpMediaSection, attr: &str) -> Option<u32> {
+    let rest = attr
+        .strip_prefix("rtpmap:")
+        .or_else(|| attr.strip_prefix("fmtp:"))?;
+    let pt = rest.split_once(' ')?.0.parse::<u32>().ok()?;
+    if m.codecs.iter().any(|c| c.payload_type == pt) {
+        None
+    } else {
+        Some(pt)
+    }
+}
+
+/// Parse a `b=` value like `AS:256` into an [`SdpBandwidth`].
+fn parse_bandwidth(value: &str) -> Result<SdpBandwidth> {
+    let (kind, amount) = value
+        .split_once(':')
+        .ok_or_else(|| SdpError::Parse(format!("b={}", value)))?;
+    let value = amount
+        .parse::<u64>()
+        .map_err(|_| SdpError::Parse(format!("b={}", value)))?;
+    Ok(SdpBandwidth {
+        kind: kind.to_string(),
+        value,
+    })
+}
+
+/// Fold an `a=` line into a media section, attaching rtpmap/fmtp lines to the
+/// codec they reference and keeping everything else as a generic attribute.
+fn apply_media_attribute(m: &mut SdpMediaSection, value: &str) {
+    if let Some(rest) = value.strip_prefix("rtpmap:") {
+        if let Some((pt, map)) = rest.split_once(' ') {
+            if let Ok(pt) = pt.parse::<u32>() {
+                if let Some(codec) = m.codecs.iter_mut().find(|c| c.payload_type == pt) {
+                    codec.rtpmap = Some(map.to_string());
+                    return;
+                }
+            }
+        }
+    } else if let Some(rest) = value.strip_prefix("fmtp:") {
+        if let Some((pt, params)) = rest.split_once(' ') {
+            if let Ok(pt) = pt.parse::<u32>() {
+                if let Some(codec) = m.codecs.iter_mut().find(|c| c.payload_type == pt) {
+                    codec.fmtp = Some(params.to_string());
+                    return;
+                }
+            }
+        }
+    }
+    m.attributes.push(value.to_string());
+}
+
+/// Whether a session-level line must be serialized *after* the `t=` line, per
+/// the RFC 4566 field order (`r= z= k= a=` follow timing).
+fn is_post_timing(line: &str) -> bool {
+    matches!(
+        line.as_bytes().first(),
+        Some(b'a') | Some(b'r') | Some(b'z') | Some(b'k')
+    ) && line.get(1..2) == Some("=")
+}
+
+/// Return the attribute name of an `a=<name>` or `a=<name>:<value>` body.
+fn attribute_name(attr: &str) -> &str {
+    match attr.split_once(':') {
+        Some((name, _)) => name,
+        None => attr,
+    }
+}
+
+/// Return a redacted copy of `sdp` safe for Debug-level logging and bug
+/// reports.
+///
+/// Full SDP leaks private IP addresses, ICE `ufrag`/`pwd`, DTLS fingerprints,
+/// and `cname`/`msid` tokens.  We strip those while keeping the description
+/// useful for debugging by remapping *statefully*: each distinct token is
+/// replaced with a stable placeholder drawn from a per-class counter, so the
+/// same token always yields the same placeholder within one pass.  Correlation
+/// across lines is preserved; the PII is not.
+pub fn anonymize(sdp: &str) -> String {
+    Anonymizer::default().run(sdp)
+}
+
+/// Per-pass remapping state, one [`HashMap`] per attribute class.  Seeding the
+/// maps per class keeps the placeholder namespaces independent.
+#[derive(Default)]
+struct Anonymizer {
+    ips:          HashMap<String, String>,
+    ufrags:       HashMap<String, String>,
+    pwds:         HashMap<String, String>,
+    fingerprints: HashMap<String, String>,
+    cnames:       HashMap<String, String>,
+    msids:        HashMap<String, String>,
+}
+
+impl Anonymizer {
+    fn run(&mut self, sdp: &str) -> String {
+        let mut out = String::new();
+        for raw in sdp.lines() {
+            out.push_str(&self.line(raw));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn line(&mut self, line: &str) -> String {
+        let (value, attr) = match line.split_once('=') {
+            Some(("a", value)) => (value, true),
+            _ => (line, false),
+        };
+
+        if attr {
+            if let Some(rest) = value.strip_prefix("ice-ufrag:") {
+                return format!("a=ice-ufrag:{}", remap(&mut self.ufrags, rest, "ice-ufrag"));
+            }
+            if let Some(rest) = value.strip_prefix("ice-pwd:") {
+                return format!("a=ice-pwd:{}", remap(&mut self.pwds, rest, "ice-pwd"));
+            }
+            if let Some(rest) = value.strip_prefix("fingerprint:") {
+                if let Some((alg, hash)) = rest.split_once(' ') {
+                    let dummy = dummy_fingerprint(remap(&mut self.fingerprints, hash, "fp"), hash);
+                    return format!("a=fingerprint:{} {}", alg, dummy);
+                }
+            }
+            if let Some(rest) = value.strip_prefix("msid:") {
+                return format!("a=msid:{}", self.msid_tokens(rest));
+            }
+        }
+
+        // Fall through: remap any embedded IPs, and any cname:/msid: tokens that
+        // ride inside another attribute (e.g. a=ssrc lines).  An `msid:<id>
+        // <track>` value spans two tokens, so the track is remapped too.
+        let mut tokens: Vec<String> = Vec::new();
+        let mut msid_track = false;
+        for tok in line.split(' ') {
+            if msid_track {
+                msid_track = false;
+                tokens.push(remap(&mut self.msids, tok, "msid"));
+                continue;
+            }
+            msid_track = tok.starts_with("msid:");
+            tokens.push(self.token(tok));
+        }
+        tokens.join(" ")
+    }
+
+    fn token(&mut self, tok: &str) -> String {
+        if let Some(rest) = tok.strip_prefix("cname:") {
+            return format!("cname:{}", remap(&mut self.cnames, rest, "cname"));
+        }
+        if let Some(rest) = tok.strip_prefix("msid:") {
+            return format!("msid:{}", self.msid_tokens(rest));
+        }
+        if tok.parse::<IpAddr>().is_ok() {
+            return remap(&mut self.ips, tok, "ip");
+        }
+        tok.to_string()
+    }
+
+    /// Remap the space-separated tokens of an msid value (`<id> <track>`).
+    fn msid_tokens(&mut self, value: &str) -> String {
+        value
+            .split(' ')
+            .map(|t| remap(&mut self.msids, t, "msid"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Look up `key` in `map`, assigning it the next `<prefix>-N` placeholder if it
+/// is not already present.  IP placeholders count up as literal addresses.
+fn remap(map: &mut HashMap<String, String>, key: &str, prefix: &str) -> String {
+    if let Some(existing) = map.get(key) {
+        return existing.clone();
+    }
+    let n = map.len();
+    let placeholder = if prefix == "ip" {
+        // 0.0.0.0, 0.0.0.1, ...
+        format!("0.0.0.{}", n)
+    } else {
+        format!("{}-{}", prefix, n)
+    };
+    map.insert(key.to_string(), placeholder.clone());
+    placeholder
+}
+
+/// Build a fixed-length dummy fingerprint the same shape (colon-separated hex
+/// byte count) as `original`, derived from its stable placeholder index.
+fn dummy_fingerprint(placeholder: String, original: &str) -> String {
+    let bytes = original.split(':').count().max(1);
+    let byte = placeholder
+        .rsplit('-')
+        .next()
+        .and_then(|n| n.parse::<u32>().ok())
+        .unwrap_or(0)
+        % 256;
+    vec![format!("{:02X}", byte); bytes].join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+o=- 42 2 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=group:BUNDLE 0\r\n\
+m=video 9 UDP/TLS/RTP/SAVPF 96 98\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=rtcp:9 IN IP4 0.0.0.0\r\n\
+a=rtpmap:96 VP8/90000\r\n\
+a=rtpmap:98 H264/90000\r\n\
+a=fmtp:98 profile-level-id=42e01f\r\n\
+";
+
+    fn video(session: &mut SdpSession) -> &mut SdpMediaSection {
+        session.media_mut("video").unwrap()
+    }
+
+    #[test]
+    fn parses_origin_timing_and_codecs() {
+        let session = SdpSession::parse(OFFER).unwrap();
+        assert_eq!(session.origin, "- 42 2 IN IP4 127.0.0.1");
+        assert_eq!(session.timing, "0 0");
+        let media = &session.media[0];
+        assert_eq!(media.media_type, "video");
+        assert_eq!(media.protocol, "UDP/TLS/RTP/SAVPF");
+        assert_eq!(
+            media.codecs.iter().map(|c| c.payload_type).collect::<Vec<_>>(),
+            vec![96, 98]
+        );
+        assert_eq!(media.codecs[1].fmtp.as_deref(), Some("profile-level-id=42e01f"));
+    }
+
+    #[test]
+    fn round_trip_is_stable_and_rfc_ordered() {
+        let once = SdpSession::parse(OFFER).unwrap().serialize().unwrap();
+        // Re-parsing the serialized form and serializing again is a fixpoint.
+        let twice = SdpSession::parse(&once).unwrap().serialize().unwrap();
+        assert_eq!(once, twice);
+        // Session timing must precede the session `a=group` line.
+        let t = once.find("t=0 0").unwrap();
+        let group = once.find("a=group:BUNDLE").unwrap();
+        assert!(t < group, "t= must come before session a= lines");
+    }
+
+    #[test]
+    fn prefer_codec_moves_match_to_front() {
+        let mut session = SdpSession::parse(OFFER).unwrap();
+        video(&mut session).prefer_codec("VP8");
+        assert_eq!(video(&mut session).codecs[0].payload_type, 96);
+        video(&mut session).prefer_codec("H264");
+        assert_eq!(video(&mut session).codecs[0].payload_type, 98);
+        // The m= format list follows the codec order after serialization.
+        let sdp = session.serialize().unwrap();
+        assert!(sdp.contains("m=video 9 UDP/TLS/RTP/SAVPF 98 96"));
+    }
+
+    #[test]
+    fn remove_codec_drops_its_rtpmap_and_fmtp() {
+        let mut session = SdpSession::parse(OFFER).unwrap();
+        video(&mut session).remove_codec("H264");
+        let sdp = session.serialize().unwrap();
+        assert!(sdp.contains("m=video 9 UDP/TLS/RTP/SAVPF 96"));
+        assert!(!sdp.contains("H264"));
+        assert!(!sdp.contains("a=fmtp:98"));
+    }
+
+    #[test]
+    fn set_bandwidth_replaces_existing_line() {
+        let mut session = SdpSession::parse(OFFER).unwrap();
+        video(&mut session).set_bandwidth("AS", 256);
+        video(&mut session).set_bandwidth("AS", 512);
+        let media = video(&mut session);
+        assert_eq!(media.bandwidths.len(), 1);
+        assert_eq!(media.bandwidths[0].value, 512);
+        assert!(session.serialize().unwrap().contains("b=AS:512"));
+    }
+
+    #[test]
+    fn removing_all_codecs_is_rejected() {
+        let mut session = SdpSession::parse(OFFER).unwrap();
+        video(&mut session).remove_codec("VP8");
+        video(&mut session).remove_codec("H264");
+        assert!(session.serialize().is_err());
+    }
+
+    #[test]
+    fn anonymize_redacts_and_remaps_stably() {
+        let sdp = "c=IN IP4 192.168.1.5\r\n\
+a=ice-ufrag:F7gR\r\n\
+a=ice-pwd:secretpassword\r\n\
+a=fingerprint:sha-256 AB:CD:EF\r\n\
+a=candidate:1 1 udp 1 192.168.1.5 5000 typ host\r\n\
+a=ssrc:111 cname:realcname\r\n\
+a=ssrc:111 msid:realstream realtrack\r\n";
+        let out = anonymize(sdp);
+
+        // PII is gone.
+        assert!(!out.contains("192.168.1.5"));
+        assert!(!out.contains("F7gR"));
+        assert!(!out.contains("secretpassword"));
+        assert!(!out.contains("realcname"));
+        assert!(!out.contains("realstream"));
+
+        // Placeholders are present and stable: the IP appears twice (c= and
+        // candidate) and maps to the same placeholder both times.
+        assert_eq!(out.matches("0.0.0.0").count(), 2);
+        assert!(out.contains("ice-ufrag:ice-ufrag-0"));
+        assert!(out.contains("cname:cname-0"));
+
+        // A second pass over the same input yields the same output.
+        assert_eq!(out, anonymize(sdp));
+    }
+}
@@ -12,28 +12,36 @@ use std::ffi::{
     CString,
 };
 use std::fmt;
+use std::future::Future;
 use std::os::raw::c_char;
 use std::ptr;
-use std::sync::{
-    Arc,
-    Mutex,
-    Condvar,
-};
+use std::sync::Mutex;
+
+use futures::executor::block_on;
+use tokio::sync::oneshot;
 
 use crate::common::Result;
 use crate::core::util::{
     RustObject,
     CppObject,
-    FutureResult,
     get_object_from_cpp,
 };
 use crate::error::RingRtcError;
+use crate::webrtc::sdp::{self, SdpSession};
 
 /// Incomplete type for SessionDescriptionInterface, used by
 /// CreateSessionDescriptionObserver callbacks.
 #[repr(C)]
 pub struct RffiSessionDescriptionInterface { _private: [u8; 0] }
 
+/// Wrapper asserting a C++ SessionDescriptionInterface pointer is safe to move
+/// between threads.  The FFI success callback fires on a WebRTC worker thread
+/// and hands the pointer to the awaiting task via a oneshot channel; Rust code
+/// never dereferences the opaque pointer, it only carries it back to C++.
+#[derive(Debug)]
+struct SendSessionDescription(*const RffiSessionDescriptionInterface);
+unsafe impl Send for SendSessionDescription {}
+
 /// Rust wrapper around WebRTC C++ SessionDescriptionInterface.
 pub struct SessionDescriptionInterface {
     /// Pointer to C++ SessionDescriptionInterface object.
@@ -74,10 +82,26 @@ impl SessionDescriptionInterface {
         } else {
             let description = unsafe { CStr::from_ptr(string_ptr).to_string_lossy().into_owned() };
             unsafe { libc::free(string_ptr as *mut libc::c_void) };
+            debug!("session description:\n{}", sdp::anonymize(&description));
             Ok(description)
         }
     }
 
+    /// Return a redacted copy of this session description safe for logging and
+    /// bug reports.  See [`sdp::anonymize`] for the remapping scheme.
+    pub fn anonymize(&self) -> Result<String> {
+        Ok(sdp::anonymize(&self.get_description()?))
+    }
+
+    /// Parse this session description into a structured [`SdpSession`] that can
+    /// be mutated (codec and bitrate policy, attribute filtering) and then
+    /// re-serialized with [`SdpSession::serialize`] before being handed back to
+    /// [`create_sdp_offer`](Self::create_sdp_offer) /
+    /// [`create_sdp_answer`](Self::create_sdp_answer).
+    pub fn parse(&self) -> Result<SdpSession> {
+        SdpSession::parse(&self.get_description()?)
+    }
+
     /// Create a SDP answer from the session description string.
     pub fn create_sdp_answer(session_desc: String) -> Result<Self> {
         let sdp = CString::new(session_desc)?;
@@ -109,11 +133,16 @@ impl SessionDescriptionInterface {
 pub struct RffiCreateSessionDescriptionObserver { _private: [u8; 0] }
 
 /// Observer object for creating a session description.
+///
+/// The FFI success/failure callbacks complete a oneshot channel from the
+/// WebRTC worker thread; callers await the result rather than blocking a
+/// condition variable, so the tokio executor is never stalled.
 #[derive(Debug)]
 pub struct CreateSessionDescriptionObserver {
-    /// condition varialbe used to signal the completion of the create
-    /// session description operation.
-    condition: FutureResult<Result<*const RffiSessionDescriptionInterface>>,
+    /// Sender half, completed once by the first `on_create_*` callback.
+    sender: Mutex<Option<oneshot::Sender<Result<SendSessionDescription>>>>,
+    /// Receiver half, taken by the first `get_result*` caller.
+    receiver: Mutex<Option<oneshot::Receiver<Result<SendSessionDescription>>>>,
     /// Pointer to C++ webrtc::rffi::CreateSessionDescriptionObserverRffi object
     rffi_csd_observer: *const RffiCreateSessionDescriptionObserver,
 }
@@ -121,59 +150,61 @@ pub struct CreateSessionDescriptionObserver {
 impl CreateSessionDescriptionObserver {
     /// Create a new CreateSessionDescriptionObserver.
     fn new() -> Self {
+        let (sender, receiver) = oneshot::channel();
         Self {
-            condition: Arc::new((Mutex::new((false, Ok(ptr::null()))), Condvar::new())),
+            sender: Mutex::new(Some(sender)),
+            receiver: Mutex::new(Some(receiver)),
             rffi_csd_observer: ptr::null(),
         }
     }
 
+    /// Complete the result channel, ignoring a closed or already-completed
+    /// channel.
+    fn complete(&self, result: Result<SendSessionDescription>) {
+        if let Ok(mut sender) = self.sender.lock() {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(result);
+            }
+        }
+    }
+
     /// Called back when the create session description operation is a
     /// success.
-    ///
-    /// This call signals the condition variable.
     fn on_create_success(&self, desc: *const RffiSessionDescriptionInterface) {
         info!("on_create_success()");
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            guard.1 = Ok(desc);
-            guard.0 = true;
-            // We notify the condvar that the value has changed.
-            cvar.notify_one();
-        }
+        self.complete(Ok(SendSessionDescription(desc)));
     }
 
     /// Called back when the create session description operation is a
     /// failure.
-    ///
-    /// This call signals the condition variable.
     fn on_create_failure(&self, err_message: String, err_type: i32) {
         warn!("on_create_failure(). error msg: {}, type: {}", err_message, err_type);
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            guard.1 = Err(RingRtcError::CreateSessionDescriptionObserver(err_message, err_type).into());
-            guard.0 = true;
-            // We notify the condvar that the value has changed.
-            cvar.notify_one();
+        self.complete(Err(RingRtcError::CreateSessionDescriptionObserver(err_message, err_type).into()));
+    }
+
+    /// Return a future that resolves to the result of the create session
+    /// description operation when the FFI callback fires.
+    pub fn get_result_future(&self) -> impl Future<Output = Result<SessionDescriptionInterface>> {
+        let receiver = self.receiver.lock().ok().and_then(|mut r| r.take());
+        async move {
+            match receiver {
+                Some(receiver) => match receiver.await {
+                    Ok(Ok(desc)) => Ok(SessionDescriptionInterface::new(desc.0)),
+                    Ok(Err(e)) => Err(RingRtcError::CreateSessionDescriptionObserverResult(format!("{}", e)).into()),
+                    Err(_) => Err(RingRtcError::CreateSessionDescriptionObserverResult("result channel closed".to_string()).into()),
+                },
+                None => Err(RingRtcError::CreateSessionDescriptionObserverResult("result already taken".to_string()).into()),
+            }
         }
     }
 
-    /// Retrieve the result of the create session description operation.
+    /// Retrieve the result of the create session description operation,
+    /// blocking until it is available.
     ///
-    /// This call blocks on the condition variable.
+    /// Thin wrapper over [`get_result_future`](Self::get_result_future) for
+    /// synchronous callers (the FFI boundary and native `CallManager` paths).
     pub fn get_result(&self) -> Result<SessionDescriptionInterface> {
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            while !guard.0 {
-                guard = cvar.wait(guard).map_err(|_| { RingRtcError::MutexPoisoned("CreateSessionDescription condvar mutex".to_string()) })?;
-            }
-            // TODO: implement guard.1.clone() here ....
-            match &guard.1 {
-                Ok(v) => Ok(SessionDescriptionInterface::new(*v)),
-                Err(e) => Err(RingRtcError::CreateSessionDescriptionObserverResult(format!("{}", e)).into()),
-            }
-        } else {
-            Err(RingRtcError::MutexPoisoned("CreateSessionDescription condvar mutex".to_string()).into())
-        }
+        block_on(self.get_result_future())
     }
 
     pub fn set_rffi_observer(&mut self, observer: *const RffiCreateSessionDescriptionObserver) {
@@ -248,11 +279,16 @@ pub fn create_csd_observer() -> Box<CreateSessionDescriptionObserver> {
 pub struct RffiSetSessionDescriptionObserver { _private: [u8; 0] }
 
 /// Observer object for setting a session description.
+///
+/// Like [`CreateSessionDescriptionObserver`], the FFI callbacks complete a
+/// oneshot channel rather than a condition variable so awaiting callers do not
+/// block the executor.
 #[derive(Debug)]
 pub struct SetSessionDescriptionObserver {
-    /// condition varialbe used to signal the completion of the set
-    /// session description operation.
-    condition: FutureResult<Result<()>>,
+    /// Sender half, completed once by the first `on_set_*` callback.
+    sender: Mutex<Option<oneshot::Sender<Result<()>>>>,
+    /// Receiver half, taken by the first `get_result*` caller.
+    receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
     /// Pointer to C++ CreateSessionDescriptionObserver object
     rffi_ssd_observer: *const RffiSetSessionDescriptionObserver,
 }
@@ -260,59 +296,61 @@ pub struct SetSessionDescriptionObserver {
 impl SetSessionDescriptionObserver {
     /// Create a new SetSessionDescriptionObserver.
     fn new() -> Self {
+        let (sender, receiver) = oneshot::channel();
         Self {
-            condition: Arc::new((Mutex::new((false, Ok(()))), Condvar::new())),
+            sender: Mutex::new(Some(sender)),
+            receiver: Mutex::new(Some(receiver)),
             rffi_ssd_observer: ptr::null(),
         }
     }
 
+    /// Complete the result channel, ignoring a closed or already-completed
+    /// channel.
+    fn complete(&self, result: Result<()>) {
+        if let Ok(mut sender) = self.sender.lock() {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(result);
+            }
+        }
+    }
+
     /// Called back when the set session description operation is a
     /// success.
-    ///
-    /// This call signals the condition variable.
     fn on_set_success(&self) {
         info!("on_set_success()");
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            guard.1 = Ok(());
-            guard.0 = true;
-            // We notify the condvar that the value has changed.
-            cvar.notify_one();
-        }
+        self.complete(Ok(()));
     }
 
     /// Called back when the set session description operation is a
     /// failure.
-    ///
-    /// This call signals the condition variable.
     fn on_set_failure(&self, err_message: String, err_type: i32) {
         warn!("on_set_failure(). error msg: {}, type: {}", err_message, err_type);
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            guard.1 = Err(RingRtcError::SetSessionDescriptionObserver(err_message, err_type).into());
-            guard.0 = true;
-            // We notify the condvar that the value has changed.
-            cvar.notify_one();
+        self.complete(Err(RingRtcError::SetSessionDescriptionObserver(err_message, err_type).into()));
+    }
+
+    /// Return a future that resolves to the result of the set session
+    /// description operation when the FFI callback fires.
+    pub fn get_result_future(&self) -> impl Future<Output = Result<()>> {
+        let receiver = self.receiver.lock().ok().and_then(|mut r| r.take());
+        async move {
+            match receiver {
+                Some(receiver) => match receiver.await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(e)) => Err(RingRtcError::SetSessionDescriptionObserverResult(format!("{}", e)).into()),
+                    Err(_) => Err(RingRtcError::SetSessionDescriptionObserverResult("result channel closed".to_string()).into()),
+                },
+                None => Err(RingRtcError::SetSessionDescriptionObserverResult("result already taken".to_string()).into()),
+            }
         }
     }
 
-    /// Retrieve the result of the create session description operation.
+    /// Retrieve the result of the set session description operation, blocking
+    /// until it is available.
     ///
-    /// This call blocks on the condition variable.
+    /// Thin wrapper over [`get_result_future`](Self::get_result_future) for
+    /// synchronous callers.
     pub fn get_result(&self) -> Result<()> {
-        let &(ref mtx, ref cvar) = &*self.condition;
-        if let Ok(mut guard) = mtx.lock() {
-            while !guard.0 {
-                guard = cvar.wait(guard).map_err(|_| { RingRtcError::MutexPoisoned("SetSessionDescription condvar mutex".to_string()) })?;
-            }
-            // TODO: implement guard.1.clone() here ....
-            match &guard.1 {
-                Ok(_) => Ok(()),
-                Err(e) => Err(RingRtcError::SetSessionDescriptionObserverResult(format!("{}", e)).into()),
-            }
-        } else {
-            Err(RingRtcError::MutexPoisoned("SetSessionDescription condvar mutex".to_string()).into())
-        }
+        block_on(self.get_result_future())
     }
 
     pub fn set_rffi_observer(&mut self, observer: *const RffiSetSessionDescriptionObserver) {
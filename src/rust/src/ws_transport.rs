@@ -0,0 +1,114 @@
+//
+// Copyright 2019-2021 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! WebSocket JSON [`SignalingTransport`] for room-based SFU signallers.
+//!
+//! Speaks a small JSON protocol: the connection opens with a `join` frame
+//! carrying an access token and a room/identity, after which
+//! [`SignalingMessage`]s and participant membership events flow in both
+//! directions as JSON text frames.
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::Message as WsMessage,
+    MaybeTlsStream,
+    WebSocketStream,
+};
+
+use ringrtc::common::Result;
+
+use crate::transport::{
+    JoinInfo, SignalingMessage, SignalingTransport, TransportEvent,
+};
+
+/// Wire frames exchanged over the WebSocket.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Frame {
+    /// Sent once on connect to authenticate and enter a room.
+    Join {
+        room:     String,
+        identity: String,
+        token:    String,
+    },
+    /// Leave the current room.
+    Leave,
+    /// A signaling payload.
+    Signal(SignalingMessage),
+    /// A participant entered the room.
+    Joined { identity: String },
+    /// A participant left the room.
+    Left { identity: String },
+}
+
+/// [`SignalingTransport`] speaking JSON over a WebSocket.
+pub struct WebSocketTransport {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WebSocketTransport {
+    /// Connect to `url` and, if `join` is supplied, send the initial join
+    /// frame carrying the access token and room/identity.
+    pub async fn connect(url: &str, join: Option<JoinInfo>) -> Result<Self> {
+        let (stream, _response) = connect_async(url).await?;
+        let mut transport = Self { stream };
+        if let Some(info) = join {
+            transport.join(&info).await?;
+        }
+        Ok(transport)
+    }
+
+    /// Serialize and write a frame as a JSON text message.
+    async fn write(&mut self, frame: Frame) -> Result<()> {
+        let json = serde_json::to_string(&frame)?;
+        self.stream.send(WsMessage::Text(json)).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SignalingTransport for WebSocketTransport {
+    async fn send(&mut self, message: SignalingMessage) -> Result<()> {
+        self.write(Frame::Signal(message)).await
+    }
+
+    async fn join(&mut self, info: &JoinInfo) -> Result<()> {
+        self.write(Frame::Join {
+            room:     info.room.clone(),
+            identity: info.identity.clone(),
+            token:    info.token.clone(),
+        })
+        .await
+    }
+
+    async fn leave(&mut self) -> Result<()> {
+        self.write(Frame::Leave).await
+    }
+
+    async fn recv(&mut self) -> Result<Option<TransportEvent>> {
+        while let Some(message) = self.stream.next().await {
+            match message? {
+                WsMessage::Text(text) => {
+                    let event = match serde_json::from_str::<Frame>(&text)? {
+                        Frame::Signal(message) => TransportEvent::Message(message),
+                        Frame::Joined { identity } => TransportEvent::ParticipantJoined(identity),
+                        Frame::Left { identity } => TransportEvent::ParticipantLeft(identity),
+                        // Join/Leave are outbound-only; ignore if echoed back.
+                        Frame::Join { .. } | Frame::Leave => continue,
+                    };
+                    return Ok(Some(event));
+                }
+                WsMessage::Close(_) => return Ok(None),
+                // Ignore binary/ping/pong control frames.
+                _ => continue,
+            }
+        }
+        Ok(None)
+    }
+}